@@ -6,18 +6,62 @@ use serde::Deserialize;
 #[serde(untagged)]
 enum CommandDef {
     Simple(String),
-    Detailed { command: String, timeout: Option<u64> },
+    Detailed {
+        command: String,
+        timeout: Option<u64>,
+        #[serde(default)]
+        tty: bool,
+        kill_timeout: Option<u64>,
+        name: Option<String>,
+        #[serde(default)]
+        depends_on: Vec<String>,
+        #[serde(default)]
+        wait_for: WaitFor,
+        ready_log: Option<String>,
+        ready_port: Option<u16>,
+    },
+}
+
+/// How a dependent waits for each of its prerequisites.
+#[derive(Deserialize, Debug, Clone, Copy, PartialEq, Eq, Default)]
+#[serde(rename_all = "lowercase")]
+pub enum WaitFor {
+    /// Wait until the prerequisite exits successfully (the default).
+    #[default]
+    Exit,
+    /// Wait until the prerequisite reports itself ready (log line or TCP port).
+    Healthy,
 }
 
 #[derive(Debug, Clone)]
 pub struct CommandSpec {
     pub command: String,
     pub timeout: Option<u64>,
+    /// Run the command attached to a pseudo-terminal so it keeps its colors.
+    pub tty: bool,
+    /// Grace period, in seconds, between SIGTERM and SIGKILL when tearing down
+    /// the command's process group. Defaults to [`DEFAULT_KILL_TIMEOUT`].
+    pub kill_timeout: Option<u64>,
+    /// Optional name other commands can `depends_on`.
+    pub name: Option<String>,
+    /// Names of commands that must reach the required state before this starts.
+    pub depends_on: Vec<String>,
+    /// Whether prerequisites must exit or merely become healthy.
+    pub wait_for: WaitFor,
+    /// Regex marking this command "healthy" when it matches an output line.
+    pub ready_log: Option<String>,
+    /// TCP port that, once connectable, marks this command "healthy".
+    pub ready_port: Option<u16>,
 }
 
+/// Default grace period before escalating from SIGTERM to SIGKILL.
+pub const DEFAULT_KILL_TIMEOUT: u64 = 5;
+
 #[derive(Deserialize, Debug)]
 struct SetsWrapper {
     sets: HashMap<String, Vec<CommandDef>>,
+    #[serde(default)]
+    watch: HashMap<String, Vec<String>>,
 }
 
 pub fn load_commands_from_yaml(path: &str, set_name: &str) -> Result<Vec<CommandSpec>, String> {
@@ -31,10 +75,19 @@ pub fn load_commands_from_yaml(path: &str, set_name: &str) -> Result<Vec<Command
                 if cmds.is_empty() {
                     return Err(format!("Set '{set_name}' in '{path}' is empty"));
                 }
-                let specs = cmds.iter().map(|d| match d {
-                    CommandDef::Simple(s) => CommandSpec { command: s.clone(), timeout: None },
-                    CommandDef::Detailed { command, timeout } => CommandSpec { command: command.clone(), timeout: *timeout },
+                let specs: Vec<CommandSpec> = cmds.iter().map(|d| match d {
+                    CommandDef::Simple(s) => CommandSpec {
+                        command: s.clone(), timeout: None, tty: false, kill_timeout: None,
+                        name: None, depends_on: Vec::new(), wait_for: WaitFor::default(),
+                        ready_log: None, ready_port: None,
+                    },
+                    CommandDef::Detailed { command, timeout, tty, kill_timeout, name, depends_on, wait_for, ready_log, ready_port } => CommandSpec {
+                        command: command.clone(), timeout: *timeout, tty: *tty, kill_timeout: *kill_timeout,
+                        name: name.clone(), depends_on: depends_on.clone(), wait_for: *wait_for,
+                        ready_log: ready_log.clone(), ready_port: *ready_port,
+                    },
                 }).collect();
+                validate_dependencies(&specs, set_name, path)?;
                 Ok(specs)
             } else {
                 let mut names: Vec<_> = w.sets.keys().cloned().collect();
@@ -50,3 +103,90 @@ pub fn load_commands_from_yaml(path: &str, set_name: &str) -> Result<Vec<Command
         }
     }
 }
+
+/// Validate the `depends_on` graph: every referenced name must exist and the
+/// graph must be acyclic. On a cycle we return a message listing it so the user
+/// can see exactly which commands form the loop.
+fn validate_dependencies(specs: &[CommandSpec], set_name: &str, path: &str) -> Result<(), String> {
+    // Map declared names to their position in the set.
+    let mut by_name: HashMap<&str, usize> = HashMap::new();
+    for (i, s) in specs.iter().enumerate() {
+        if let Some(n) = &s.name {
+            by_name.insert(n.as_str(), i);
+        }
+    }
+
+    for s in specs {
+        for dep in &s.depends_on {
+            if !by_name.contains_key(dep.as_str()) {
+                return Err(format!(
+                    "Command in set '{set_name}' of '{path}' depends on unknown command '{dep}'"
+                ));
+            }
+        }
+    }
+
+    // Iterative DFS with a three-colour marking to find a cycle.
+    #[derive(Clone, Copy, PartialEq)]
+    enum Mark { Unseen, Active, Done }
+    let mut marks = vec![Mark::Unseen; specs.len()];
+    let mut stack: Vec<usize> = Vec::new();
+
+    for start in 0..specs.len() {
+        if marks[start] != Mark::Unseen {
+            continue;
+        }
+        stack.push(start);
+        let mut path_stack: Vec<usize> = Vec::new();
+        while let Some(&node) = stack.last() {
+            match marks[node] {
+                Mark::Unseen => {
+                    marks[node] = Mark::Active;
+                    path_stack.push(node);
+                    for dep in &specs[node].depends_on {
+                        let target = by_name[dep.as_str()];
+                        if marks[target] == Mark::Active {
+                            // Found a back-edge: reconstruct the cycle.
+                            let from = path_stack.iter().position(|&n| n == target).unwrap_or(0);
+                            let cycle: Vec<String> = path_stack[from..]
+                                .iter()
+                                .map(|&n| specs[n].name.clone().unwrap_or_else(|| specs[n].command.clone()))
+                                .collect();
+                            return Err(format!(
+                                "Dependency cycle in set '{set_name}' of '{path}': {} -> {}",
+                                cycle.join(" -> "),
+                                cycle.first().cloned().unwrap_or_default()
+                            ));
+                        }
+                        if marks[target] == Mark::Unseen {
+                            stack.push(target);
+                        }
+                    }
+                }
+                Mark::Active => {
+                    marks[node] = Mark::Done;
+                    path_stack.pop();
+                    stack.pop();
+                }
+                Mark::Done => {
+                    stack.pop();
+                }
+            }
+        }
+    }
+    Ok(())
+}
+
+/// Load the `watch:` globs scoped to `set_name`, if any are configured.
+///
+/// The `watch` map lives next to `sets` and lets a set declare which paths
+/// should trigger a re-run in watch mode. A missing entry yields an empty
+/// list, meaning "watch everything".
+pub fn load_watch_globs(path: &str, set_name: &str) -> Result<Vec<String>, String> {
+    let text = fs::read_to_string(path)
+        .map_err(|e| format!("Failed to read YAML file '{path}': {e}"))?;
+    match serde_yaml::from_str::<SetsWrapper>(&text) {
+        Ok(w) => Ok(w.watch.get(set_name).cloned().unwrap_or_default()),
+        Err(e2) => Err(format!("Failed to parse YAML in '{path}': {e2}")),
+    }
+}
@@ -3,6 +3,7 @@ use std::env;
 mod config;
 mod runner;
 mod tui;
+mod watch;
 
 const APP_VERSION: &str = concat!(env!("CARGO_PKG_VERSION"), " (", env!("GIT_COMMIT_HASH"), ")");
 
@@ -10,11 +11,15 @@ const APP_VERSION: &str = concat!(env!("CARGO_PKG_VERSION"), " (", env!("GIT_COM
 struct Options {
     tui: bool,
     config_file: Option<String>,
+    watch: bool,
+    watch_globs: Vec<String>,
+    pty: bool,
+    log_dir: Option<String>,
 }
 
 fn print_help() {
     println!(
-        "ghop [options] <set-name>\n\nGhop reads commands from a YAML file (ghop.yml by default) and runs the named set.\n\nOptions:\n    -h, --help            Print this help message.\n    -v, --version         Print the version.\n    -t, --tui             Run in TUI mode.\n    -f, --file <FILE>     YAML file to load (default: ghop.yml).\n\nYAML format example (only supported format):\n    sets:\n      dev: [\"npm run dev\", \"cargo watch -x run\"]\n\nExamples:\n    ghop build\n    ghop -f ghop.yml dev\n"
+        "ghop [options] <set-name>\n\nGhop reads commands from a YAML file (ghop.yml by default) and runs the named set.\n\nOptions:\n    -h, --help            Print this help message.\n    -v, --version         Print the version.\n    -t, --tui             Run in TUI mode.\n    -w, --watch [GLOB]    Keep running, re-running the set when files change.\n    -p, --pty             Run every command under a pseudo-terminal.\n    -l, --logs <DIR>      (TUI) Tee each command's output to a log file in DIR.\n    -f, --file <FILE>     YAML file to load (default: ghop.yml).\n\nYAML format example (only supported format):\n    sets:\n      dev: [\"npm run dev\", \"cargo watch -x run\"]\n\nExamples:\n    ghop build\n    ghop -f ghop.yml dev\n"
     );
 }
 
@@ -46,6 +51,22 @@ fn main() {
                 opts.tui = true;
                 i += 1;
             }
+            "-w" | "--watch" => {
+                opts.watch = true;
+                i += 1;
+            }
+            "-p" | "--pty" => {
+                opts.pty = true;
+                i += 1;
+            }
+            "-l" | "--logs" => {
+                if i + 1 >= args.len() {
+                    eprintln!("-l/--logs requires a directory path");
+                    std::process::exit(2);
+                }
+                opts.log_dir = Some(args[i + 1].clone());
+                i += 2;
+            }
             "-f" | "--file" => {
                 if i + 1 >= args.len() {
                     eprintln!("-f/--file requires a file path");
@@ -68,20 +89,36 @@ fn main() {
         eprintln!("No set specified. Provide a set name to run (e.g., 'ghop build').");
         std::process::exit(1);
     }
-    let set_name = args[i].clone();
-    let commands = match config::load_commands_from_yaml(&cfg_path, &set_name) {
+    // In watch mode, any positionals before the set name are watch globs:
+    //   ghop -w src tests dev  =>  globs = [src, tests], set = dev
+    let positionals = &args[i..];
+    let set_name = if opts.watch && positionals.len() > 1 {
+        opts.watch_globs = positionals[..positionals.len() - 1].to_vec();
+        positionals[positionals.len() - 1].clone()
+    } else {
+        positionals[0].clone()
+    };
+    let mut commands = match config::load_commands_from_yaml(&cfg_path, &set_name) {
         Ok(cmds) => cmds,
         Err(e) => {
             eprintln!("{}", e);
             std::process::exit(1);
         }
     };
+    // `--pty` forces every command in the set onto a pseudo-terminal.
+    if opts.pty {
+        for c in &mut commands {
+            c.tty = true;
+        }
+    }
 
     if opts.tui {
         // Run async TUI mode (currently ignores per-command timeouts in TUI)
         let commands_str: Vec<String> = commands.iter().map(|c| c.command.clone()).collect();
         let rt = tokio::runtime::Builder::new_multi_thread().enable_io().enable_time().build().expect("tokio runtime");
-        match rt.block_on(tui::run(commands_str)) {
+        // `--watch` with `--tui` turns each pane into a restart-on-exit monitor.
+        let log_dir = opts.log_dir.clone().map(std::path::PathBuf::from);
+        match rt.block_on(tui::run(commands_str, opts.watch, log_dir)) {
             Ok(code) => {
                 if code != 0 { std::process::exit(code); }
                 return;
@@ -93,6 +130,23 @@ fn main() {
         }
     }
 
+    if opts.watch {
+        // Globs from the config `watch:` key extend any passed on the CLI.
+        let mut globs = opts.watch_globs.clone();
+        match config::load_watch_globs(&cfg_path, &set_name) {
+            Ok(mut from_cfg) => globs.append(&mut from_cfg),
+            Err(e) => {
+                eprintln!("{}", e);
+                std::process::exit(1);
+            }
+        }
+        let code = runner::run_commands_watched(commands, globs);
+        if code != 0 {
+            std::process::exit(code);
+        }
+        return;
+    }
+
     let code = runner::run_commands(commands);
     if code != 0 {
         std::process::exit(code);
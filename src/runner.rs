@@ -1,117 +1,829 @@
-use std::io::{BufRead, BufReader};
 use std::process::{Command, Stdio};
 use std::sync::{Arc, Mutex, atomic::{AtomicBool, Ordering}};
 use std::thread;
+use std::time::{Duration, Instant};
 
-use crate::config::CommandSpec;
+use crate::config::{CommandSpec, WaitFor, DEFAULT_KILL_TIMEOUT};
 
-fn run_command(label: String, spec: CommandSpec, print_lock: Arc<Mutex<()>>) -> i32 {
-    let cmd = spec.command.clone();
+/// Called with each output line of a command, used by dependency scheduling to
+/// detect a `ready_log` match without disturbing the normal labeled printing.
+pub(crate) type LineHook = Arc<dyn Fn(&str) + Send + Sync>;
 
-    // Determine a shell based on a platform
-    #[cfg(windows)]
-    let mut child = Command::new("cmd")
-        .arg("/C")
-        .arg(&cmd)
-        .stdout(Stdio::piped())
-        .stderr(Stdio::piped())
-        .spawn()
-        .expect("failed to spawn process");
-
-    #[cfg(not(windows))]
-    let mut child = Command::new("sh")
-        .arg("-c")
-        .arg(&cmd)
-        .stdout(Stdio::piped())
-        .stderr(Stdio::piped())
-        .spawn()
-        .expect("failed to spawn process");
+/// Process-group control on Unix: each command is made a group leader so we can
+/// signal its whole tree (grandchildren included) rather than just the `sh -c`
+/// wrapper, and so a single Ctrl-C can tear every concurrent group down.
+#[cfg(unix)]
+mod procgroup {
+    use super::*;
+    use std::sync::{Once, OnceLock};
 
-    let stdout = child.stdout.take().expect("failed to capture stdout");
-    let stderr = child.stderr.take().expect("failed to capture stderr");
+    /// Set once ghop receives SIGINT/SIGTERM; every poll loop observes it and
+    /// tears its own group down, so Ctrl-C drains all concurrent commands.
+    static SHUTDOWN: AtomicBool = AtomicBool::new(false);
 
-    // Shared child for watchdog
-    let child_arc = Arc::new(Mutex::new(child));
-    let timed_out = Arc::new(AtomicBool::new(false));
-
-    // Watchdog thread if timeout specified
-    if let Some(secs) = spec.timeout {
-        let child_arc_wd = Arc::clone(&child_arc);
-        let timed_out_wd = Arc::clone(&timed_out);
-        thread::spawn(move || {
-            std::thread::sleep(std::time::Duration::from_secs(secs));
-            // Check if still running and kill
-            let mut ch = child_arc_wd.lock().unwrap();
-            if let Ok(None) = ch.try_wait() {
-                // Still running
-                let _ = ch.kill();
-                timed_out_wd.store(true, Ordering::SeqCst);
-            }
+    pub(super) fn shutting_down() -> bool {
+        SHUTDOWN.load(Ordering::SeqCst)
+    }
+
+    /// Process-group ids of every currently-running command, so the signal
+    /// forwarder can reach all of them when ghop itself is interrupted.
+    fn registry() -> &'static Mutex<Vec<libc::pid_t>> {
+        static REG: OnceLock<Mutex<Vec<libc::pid_t>>> = OnceLock::new();
+        REG.get_or_init(|| Mutex::new(Vec::new()))
+    }
+
+    pub(super) fn register(pgid: libc::pid_t) {
+        registry().lock().unwrap().push(pgid);
+    }
+
+    pub(super) fn unregister(pgid: libc::pid_t) {
+        registry().lock().unwrap().retain(|&p| p != pgid);
+    }
+
+    pub(super) fn sigterm(pgid: libc::pid_t) {
+        unsafe { libc::killpg(pgid, libc::SIGTERM) };
+    }
+
+    pub(super) fn sigkill(pgid: libc::pid_t) {
+        unsafe { libc::killpg(pgid, libc::SIGKILL) };
+    }
+
+    /// Install a one-shot SIGINT/SIGTERM forwarder. When ghop receives either
+    /// signal it SIGTERMs every running group; the per-command poll loops then
+    /// observe the cancel flag, escalate to SIGKILL after the grace period, and
+    /// drain normally, so a single Ctrl-C cleanly tears everything down.
+    pub(super) fn install_signal_forwarding() {
+        static INSTALLED: Once = Once::new();
+        INSTALLED.call_once(|| {
+            use signal_hook::consts::{SIGINT, SIGTERM};
+            use signal_hook::iterator::Signals;
+
+            let mut signals = match Signals::new([SIGINT, SIGTERM]) {
+                Ok(s) => s,
+                Err(_) => return,
+            };
+            thread::spawn(move || {
+                for _ in signals.forever() {
+                    SHUTDOWN.store(true, Ordering::SeqCst);
+                    for &pgid in registry().lock().unwrap().iter() {
+                        sigterm(pgid);
+                    }
+                }
+            });
         });
     }
+}
 
-    let print_lock_clone = Arc::clone(&print_lock);
-    let label_out = label.clone();
-    let t_out = thread::spawn(move || {
-        let reader = BufReader::new(stdout);
-        for line in reader.lines() {
-            let line = line.unwrap_or_default();
-            let _g = print_lock_clone.lock().unwrap();
-            println!("[{label_out}] {line}");
+/// Two-phase group teardown driven from a poll loop: SIGTERM on the first call,
+/// then SIGKILL once `grace` seconds have elapsed without the group exiting.
+#[cfg(unix)]
+fn escalate(pgid: libc::pid_t, termed_at: &mut Option<Instant>, grace: u64) {
+    match termed_at {
+        None => {
+            procgroup::sigterm(pgid);
+            *termed_at = Some(Instant::now());
         }
-    });
+        Some(t) if t.elapsed() >= Duration::from_secs(grace) => procgroup::sigkill(pgid),
+        _ => {}
+    }
+}
 
-    let print_lock_clone = Arc::clone(&print_lock);
-    let label_err = label.clone();
-    let t_err = thread::spawn(move || {
-        let reader = BufReader::new(stderr);
-        for line in reader.lines() {
-            let line = line.unwrap_or_default();
-            let _g = print_lock_clone.lock().unwrap();
-            eprintln!("[{label_err}][err] {line}");
-        }
-    });
+/// Run a single command to completion and return its exit code.
+///
+/// Piped commands go through the shared async execution core: each is spawned
+/// with tokio's async process API, its output is streamed by an async reader,
+/// and the exit is awaited directly — no `Arc<Mutex<Child>>`, no 10ms polling
+/// loop, and no separate watchdog thread. The timeout is a `tokio::time::timeout`
+/// around the wait future; cancellation (watch reruns, Ctrl-C) is checked on a
+/// 100ms tick that runs alongside the wait. `tty` commands do not use this core:
+/// they run on a blocking task in `pty::run_command_pty`, which polls
+/// `try_wait` every 10ms and enforces the timeout from a watchdog thread.
+async fn run_command(
+    label: String,
+    spec: CommandSpec,
+    print_lock: Arc<Mutex<()>>,
+    cancel: Arc<AtomicBool>,
+    on_line: Option<LineHook>,
+) -> i32 {
+    #[cfg(unix)]
+    if spec.tty {
+        // A PTY needs raw fd plumbing that has no async analogue; run it on a
+        // blocking task so it doesn't stall the executor.
+        return tokio::task::spawn_blocking(move || {
+            pty::run_command_pty(label, spec, print_lock, cancel, on_line)
+        })
+        .await
+        .unwrap_or(-1);
+    }
+
+    let grace = spec.kill_timeout.unwrap_or(DEFAULT_KILL_TIMEOUT);
+    #[cfg(windows)]
+    let _ = grace;
 
-    // Wait for a child using non-blocking polling to allow watchdog to acquire the lock
-    let code = loop {
+    // Build the platform shell command, then hand it to tokio.
+    let std_cmd = {
+        #[cfg(windows)]
+        {
+            let mut c = Command::new("cmd");
+            c.arg("/C").arg(&spec.command).stdout(Stdio::piped()).stderr(Stdio::piped());
+            c
+        }
+        #[cfg(not(windows))]
         {
-            let mut ch = child_arc.lock().unwrap();
-            match ch.try_wait() {
-                Ok(Some(status)) => break status.code().unwrap_or(-1),
-                Ok(None) => { /* still running */ }
-                Err(_) => break -1,
+            use std::os::unix::process::CommandExt;
+            let mut c = Command::new("sh");
+            c.arg("-c").arg(&spec.command).stdout(Stdio::piped()).stderr(Stdio::piped());
+            // Become a process-group leader so teardown can signal the whole
+            // tree (e.g. `npm run dev` and the node it spawns), not just `sh`.
+            unsafe {
+                c.pre_exec(|| {
+                    if libc::setpgid(0, 0) == -1 {
+                        return Err(std::io::Error::last_os_error());
+                    }
+                    Ok(())
+                });
+            }
+            c
+        }
+    };
+    let mut cmd = tokio::process::Command::from(std_cmd);
+    cmd.kill_on_drop(true);
+    let mut child = match cmd.spawn() {
+        Ok(c) => c,
+        Err(e) => {
+            let _g = print_lock.lock().unwrap();
+            eprintln!("[{label}][err] failed to spawn process: {e}");
+            return -1;
+        }
+    };
+
+    let stdout = child.stdout.take().expect("failed to capture stdout");
+    let stderr = child.stderr.take().expect("failed to capture stderr");
+
+    // The leader's pid is also its process-group id.
+    #[cfg(unix)]
+    let pgid = child.id().unwrap_or(0) as libc::pid_t;
+    #[cfg(unix)]
+    procgroup::register(pgid);
+
+    // Stream both pipes through one async reader, preserving interleaving.
+    let reader = tokio::spawn(pump(stdout, stderr, label.clone(), Arc::clone(&print_lock), on_line));
+
+    // Await exit, racing a timeout if one is configured.
+    let (code, timed_out) = match spec.timeout {
+        Some(secs) => {
+            match tokio::time::timeout(
+                Duration::from_secs(secs),
+                wait_cancellable(&mut child, &cancel, grace),
+            )
+            .await
+            {
+                Ok(code) => (code, false),
+                Err(_) => {
+                    // Timed out: tear the group down, then reap.
+                    kill_now(&mut child, grace).await;
+                    (124, true)
+                }
             }
         }
-        std::thread::sleep(std::time::Duration::from_millis(10));
+        None => (wait_cancellable(&mut child, &cancel, grace).await, false),
     };
 
-    // Wait for output threads (they should exit when pipes close)
-    let _ = t_out.join();
-    let _ = t_err.join();
+    #[cfg(unix)]
+    procgroup::unregister(pgid);
+    let _ = reader.await;
 
-    if timed_out.load(Ordering::SeqCst) {
-        // Print a timeout message labeled
+    if timed_out {
         let _g = print_lock.lock().unwrap();
         eprintln!("[{label}][err] command timed out after {}s", spec.timeout.unwrap_or(0));
-        return 124; // commonly used timeout exit code
     }
-
     code
 }
 
+/// Await the child's exit, stepping in to tear its group down when a cancel or
+/// shutdown is signalled. The tick only gates cancellation checks; normal exit
+/// detection comes straight from `child.wait()`.
+async fn wait_cancellable(
+    child: &mut tokio::process::Child,
+    cancel: &Arc<AtomicBool>,
+    grace: u64,
+) -> i32 {
+    #[cfg(unix)]
+    let pgid = child.id().unwrap_or(0) as libc::pid_t;
+    let mut termed_at: Option<Instant> = None;
+    let mut tick = tokio::time::interval(Duration::from_millis(100));
+    loop {
+        tokio::select! {
+            status = child.wait() => {
+                return status.ok().and_then(|s| s.code()).unwrap_or(-1);
+            }
+            _ = tick.tick() => {
+                #[cfg(unix)]
+                if cancel.load(Ordering::SeqCst) || procgroup::shutting_down() {
+                    escalate(pgid, &mut termed_at, grace);
+                }
+                #[cfg(not(unix))]
+                if cancel.load(Ordering::SeqCst) {
+                    let _ = child.start_kill();
+                    let _ = (&mut termed_at, grace);
+                }
+            }
+        }
+    }
+}
+
+/// SIGTERM the child's group, wait out the grace period, then SIGKILL and reap.
+async fn kill_now(child: &mut tokio::process::Child, grace: u64) {
+    #[cfg(unix)]
+    {
+        let pgid = child.id().unwrap_or(0) as libc::pid_t;
+        procgroup::sigterm(pgid);
+        if tokio::time::timeout(Duration::from_secs(grace), child.wait()).await.is_err() {
+            procgroup::sigkill(pgid);
+            let _ = child.wait().await;
+        }
+    }
+    #[cfg(not(unix))]
+    {
+        let _ = grace;
+        let _ = child.kill().await;
+    }
+}
+
+/// Combined reader: drains stdout and stderr in readiness order so a program's
+/// lines stay in the order it produced them, tagging stderr `[err]`.
+///
+/// On Unix this is a single `read2`-style reader — both pipe fds are set
+/// `O_NONBLOCK` and multiplexed with `poll()`, reading whatever is ready into a
+/// per-fd buffer and splitting complete lines out (buffering any partial
+/// trailing line until the next read) — run on a blocking task so it does not
+/// stall the async runtime. Other platforms fall back to two async line
+/// streams feeding the same emitter.
+async fn pump(
+    stdout: tokio::process::ChildStdout,
+    stderr: tokio::process::ChildStderr,
+    label: String,
+    print_lock: Arc<Mutex<()>>,
+    on_line: Option<LineHook>,
+) {
+    #[cfg(unix)]
+    {
+        let _ = tokio::task::spawn_blocking(move || {
+            pump_poll(stdout, stderr, &label, &print_lock, &on_line)
+        })
+        .await;
+    }
+    #[cfg(not(unix))]
+    {
+        use tokio::io::{AsyncBufReadExt, BufReader};
+        let mut out = BufReader::new(stdout).lines();
+        let mut err = BufReader::new(stderr).lines();
+        let mut out_done = false;
+        let mut err_done = false;
+        while !(out_done && err_done) {
+            tokio::select! {
+                line = out.next_line(), if !out_done => match line {
+                    Ok(Some(l)) => emit(&label, false, &l, &print_lock, &on_line),
+                    _ => out_done = true,
+                },
+                line = err.next_line(), if !err_done => match line {
+                    Ok(Some(l)) => emit(&label, true, &l, &print_lock, &on_line),
+                    _ => err_done = true,
+                },
+            }
+        }
+    }
+}
+
+/// Single non-blocking reader over both pipes, polling for readiness and
+/// emitting complete lines in the order the bytes arrive.
+#[cfg(unix)]
+fn pump_poll(
+    stdout: tokio::process::ChildStdout,
+    stderr: tokio::process::ChildStderr,
+    label: &str,
+    print_lock: &Arc<Mutex<()>>,
+    on_line: &Option<LineHook>,
+) {
+    use std::os::unix::io::AsRawFd;
+
+    // Own the handles for the whole read so the fds stay valid.
+    let out_fd = stdout.as_raw_fd();
+    let err_fd = stderr.as_raw_fd();
+    set_nonblocking(out_fd);
+    set_nonblocking(err_fd);
+
+    // Per-fd buffer for the partial trailing line carried between reads.
+    let mut out_buf: Vec<u8> = Vec::new();
+    let mut err_buf: Vec<u8> = Vec::new();
+    let mut out_done = false;
+    let mut err_done = false;
+
+    let mut chunk = [0u8; 4096];
+    while !(out_done && err_done) {
+        let mut fds = [
+            libc::pollfd { fd: if out_done { -1 } else { out_fd }, events: libc::POLLIN, revents: 0 },
+            libc::pollfd { fd: if err_done { -1 } else { err_fd }, events: libc::POLLIN, revents: 0 },
+        ];
+        let rc = unsafe { libc::poll(fds.as_mut_ptr(), fds.len() as libc::nfds_t, -1) };
+        if rc < 0 {
+            if std::io::Error::last_os_error().kind() == std::io::ErrorKind::Interrupted {
+                continue;
+            }
+            break;
+        }
+        // stdout first, then stderr, draining each that is ready this cycle.
+        if !out_done && fds[0].revents != 0 {
+            out_done = drain_fd(out_fd, &mut chunk, &mut out_buf, label, false, print_lock, on_line);
+        }
+        if !err_done && fds[1].revents != 0 {
+            err_done = drain_fd(err_fd, &mut chunk, &mut err_buf, label, true, print_lock, on_line);
+        }
+    }
+
+    // Emit any unterminated trailing line on each stream.
+    flush_buf(&out_buf, label, false, print_lock, on_line);
+    flush_buf(&err_buf, label, true, print_lock, on_line);
+}
+
+/// Read everything currently available on `fd`, emit the complete lines it
+/// yields, and report whether the stream has reached end-of-file.
+#[cfg(unix)]
+fn drain_fd(
+    fd: libc::c_int,
+    chunk: &mut [u8],
+    buf: &mut Vec<u8>,
+    label: &str,
+    is_err: bool,
+    print_lock: &Arc<Mutex<()>>,
+    on_line: &Option<LineHook>,
+) -> bool {
+    loop {
+        let n = unsafe { libc::read(fd, chunk.as_mut_ptr() as *mut libc::c_void, chunk.len()) };
+        if n > 0 {
+            buf.extend_from_slice(&chunk[..n as usize]);
+            while let Some(pos) = buf.iter().position(|&b| b == b'\n') {
+                let mut line: Vec<u8> = buf.drain(..=pos).collect();
+                line.pop();
+                if line.last() == Some(&b'\r') { line.pop(); }
+                emit(label, is_err, &String::from_utf8_lossy(&line), print_lock, on_line);
+            }
+            continue;
+        }
+        if n == 0 {
+            return true; // EOF
+        }
+        // n < 0: EAGAIN means drained for now; anything else ends the stream.
+        let err = std::io::Error::last_os_error();
+        return !matches!(err.kind(), std::io::ErrorKind::WouldBlock | std::io::ErrorKind::Interrupted);
+    }
+}
+
+/// Emit a trailing partial line (no newline terminator) if one remains.
+#[cfg(unix)]
+fn flush_buf(
+    buf: &[u8],
+    label: &str,
+    is_err: bool,
+    print_lock: &Arc<Mutex<()>>,
+    on_line: &Option<LineHook>,
+) {
+    if !buf.is_empty() {
+        emit(label, is_err, &String::from_utf8_lossy(buf), print_lock, on_line);
+    }
+}
+
+/// Set `O_NONBLOCK` on a raw fd so reads return `EAGAIN` instead of blocking.
+#[cfg(unix)]
+fn set_nonblocking(fd: libc::c_int) {
+    unsafe {
+        let flags = libc::fcntl(fd, libc::F_GETFL);
+        if flags >= 0 {
+            libc::fcntl(fd, libc::F_SETFL, flags | libc::O_NONBLOCK);
+        }
+    }
+}
+
+/// Print one labeled line, notifying the readiness hook first.
+fn emit(label: &str, is_err: bool, line: &str, print_lock: &Arc<Mutex<()>>, on_line: &Option<LineHook>) {
+    if let Some(hook) = on_line {
+        hook(line);
+    }
+    let _g = print_lock.lock().unwrap();
+    if is_err {
+        eprintln!("[{label}][err] {line}");
+    } else {
+        println!("[{label}] {line}");
+    }
+}
+
+/// PTY-backed execution: each `tty: true` command runs attached to a
+/// pseudo-terminal so it believes it is talking to an interactive terminal and
+/// keeps its colors and progress output. Unlike the piped path it reads the
+/// master with a blocking reader thread, waits on a 10ms `try_wait` loop, and
+/// enforces the timeout from its own watchdog thread; lines get the same
+/// `[label]` prefix, but stdout and stderr arrive merged and none is tagged `[err]`.
+#[cfg(unix)]
+mod pty {
+    use super::*;
+    use std::fs::File;
+    use std::io::{BufRead, BufReader};
+    use std::os::unix::io::{FromRawFd, RawFd};
+    use std::os::unix::process::CommandExt;
+    use std::process::Command;
+    use std::sync::{Once, OnceLock};
+
+    /// Master fds of every live PTY, so a terminal resize reaches all of them —
+    /// not just the last one spawned. Mirrors `procgroup::registry`.
+    fn registry() -> &'static Mutex<Vec<RawFd>> {
+        static REG: OnceLock<Mutex<Vec<RawFd>>> = OnceLock::new();
+        REG.get_or_init(|| Mutex::new(Vec::new()))
+    }
+
+    fn register_master(fd: RawFd) {
+        registry().lock().unwrap().push(fd);
+    }
+
+    fn unregister_master(fd: RawFd) {
+        registry().lock().unwrap().retain(|&f| f != fd);
+    }
+
+    /// Install a one-shot SIGWINCH forwarder that resizes every live PTY master
+    /// on each terminal resize. Like the SIGINT/SIGTERM forwarder, this runs on
+    /// a dedicated thread via `signal-hook`, so it can safely lock the registry
+    /// (a raw signal handler could not) and full-screen children in every pane
+    /// re-layout correctly.
+    fn install_winch_forwarding() {
+        static INSTALLED: Once = Once::new();
+        INSTALLED.call_once(|| {
+            use signal_hook::consts::SIGWINCH;
+            use signal_hook::iterator::Signals;
+
+            let mut signals = match Signals::new([SIGWINCH]) {
+                Ok(s) => s,
+                Err(_) => return,
+            };
+            thread::spawn(move || {
+                for _ in signals.forever() {
+                    let ws = parent_winsize();
+                    for &fd in registry().lock().unwrap().iter() {
+                        unsafe { libc::ioctl(fd, libc::TIOCSWINSZ, &ws) };
+                    }
+                }
+            });
+        });
+    }
+
+    /// Read the controlling terminal's size, falling back to a sane default.
+    fn parent_winsize() -> libc::winsize {
+        let mut ws: libc::winsize = unsafe { std::mem::zeroed() };
+        let ok = unsafe { libc::ioctl(libc::STDIN_FILENO, libc::TIOCGWINSZ, &mut ws) } == 0;
+        if !ok || ws.ws_row == 0 || ws.ws_col == 0 {
+            ws.ws_row = 24;
+            ws.ws_col = 80;
+        }
+        ws
+    }
+
+    pub(super) fn run_command_pty(
+        label: String,
+        spec: CommandSpec,
+        print_lock: Arc<Mutex<()>>,
+        cancel: Arc<AtomicBool>,
+        on_line: Option<LineHook>,
+    ) -> i32 {
+        let mut master: RawFd = -1;
+        let mut slave: RawFd = -1;
+        let ws = parent_winsize();
+        let rc = unsafe {
+            libc::openpty(
+                &mut master,
+                &mut slave,
+                std::ptr::null_mut(),
+                std::ptr::null(),
+                &ws,
+            )
+        };
+        if rc != 0 {
+            let _g = print_lock.lock().unwrap();
+            eprintln!("[{label}][err] failed to allocate pty");
+            return -1;
+        }
+
+        let cmd = spec.command.clone();
+        let mut command = Command::new("sh");
+        command.arg("-c").arg(&cmd);
+        // The child becomes its own session leader with the slave as its
+        // controlling terminal; stdin/stdout/stderr all point at the slave.
+        unsafe {
+            command.pre_exec(move || {
+                if libc::setsid() == -1 {
+                    return Err(std::io::Error::last_os_error());
+                }
+                if libc::ioctl(slave, libc::TIOCSCTTY as _, 0) == -1 {
+                    return Err(std::io::Error::last_os_error());
+                }
+                for target in [libc::STDIN_FILENO, libc::STDOUT_FILENO, libc::STDERR_FILENO] {
+                    if libc::dup2(slave, target) == -1 {
+                        return Err(std::io::Error::last_os_error());
+                    }
+                }
+                if slave > libc::STDERR_FILENO {
+                    libc::close(slave);
+                }
+                libc::close(master);
+                Ok(())
+            });
+        }
+
+        let child = match command.spawn() {
+            Ok(c) => c,
+            Err(e) => {
+                unsafe {
+                    libc::close(master);
+                    libc::close(slave);
+                }
+                let _g = print_lock.lock().unwrap();
+                eprintln!("[{label}][err] failed to spawn process: {e}");
+                return -1;
+            }
+        };
+        // The slave belongs to the child now.
+        unsafe { libc::close(slave) };
+
+        // `setsid` made the child a session (and group) leader: pgid == pid.
+        let pgid = child.id() as libc::pid_t;
+        let grace = spec.kill_timeout.unwrap_or(DEFAULT_KILL_TIMEOUT);
+        procgroup::register(pgid);
+
+        // Route SIGWINCH to this master (alongside any other live PTYs) so
+        // full-screen children resize.
+        register_master(master);
+        install_winch_forwarding();
+
+        let child_arc = Arc::new(Mutex::new(child));
+        let timed_out = Arc::new(AtomicBool::new(false));
+        if let Some(secs) = spec.timeout {
+            let child_arc_wd = Arc::clone(&child_arc);
+            let timed_out_wd = Arc::clone(&timed_out);
+            thread::spawn(move || {
+                std::thread::sleep(Duration::from_secs(secs));
+                let mut ch = child_arc_wd.lock().unwrap();
+                if let Ok(None) = ch.try_wait() {
+                    timed_out_wd.store(true, Ordering::SeqCst);
+                    procgroup::sigterm(pgid);
+                    drop(ch);
+                    std::thread::sleep(Duration::from_secs(grace));
+                    ch = child_arc_wd.lock().unwrap();
+                    if let Ok(None) = ch.try_wait() {
+                        procgroup::sigkill(pgid);
+                    }
+                }
+            });
+        }
+
+        // A PTY exposes a single combined stream; reuse the `[label]` prefixing.
+        let reader_master = unsafe { File::from_raw_fd(master) };
+        let print_lock_out = Arc::clone(&print_lock);
+        let label_out = label.clone();
+        let on_line_out = on_line.clone();
+        let t_out = thread::spawn(move || {
+            let reader = BufReader::new(reader_master);
+            for line in reader.lines() {
+                // A closed PTY master reads as EIO; treat that as end-of-stream.
+                let line = match line {
+                    Ok(l) => l,
+                    Err(_) => break,
+                };
+                if let Some(hook) = &on_line_out {
+                    hook(&line);
+                }
+                let _g = print_lock_out.lock().unwrap();
+                println!("[{label_out}] {line}");
+            }
+        });
+
+        let mut termed_at: Option<Instant> = None;
+        let code = loop {
+            {
+                let mut ch = child_arc.lock().unwrap();
+                match ch.try_wait() {
+                    Ok(Some(status)) => break status.code().unwrap_or(-1),
+                    Ok(None) => {
+                        if cancel.load(Ordering::SeqCst) || procgroup::shutting_down() {
+                            escalate(pgid, &mut termed_at, grace);
+                        }
+                    }
+                    Err(_) => break -1,
+                }
+            }
+            std::thread::sleep(Duration::from_millis(10));
+        };
+
+        let _ = t_out.join();
+        procgroup::unregister(pgid);
+        unregister_master(master);
+
+        if timed_out.load(Ordering::SeqCst) {
+            let _g = print_lock.lock().unwrap();
+            eprintln!("[{label}][err] command timed out after {}s", spec.timeout.unwrap_or(0));
+            return 124;
+        }
+        code
+    }
+}
+
 pub fn run_commands(commands: Vec<CommandSpec>) -> i32 {
+    #[cfg(unix)]
+    raise_nofile();
+    run_set(commands, Arc::new(AtomicBool::new(false)))
+}
+
+/// Raise the soft open-file limit toward the hard cap before launching a set.
+///
+/// Each command holds several descriptors (two pipes, more with a PTY), so a
+/// large set can exhaust the default `RLIMIT_NOFILE` — 256 on macOS — and fail
+/// to spawn with "too many open files". We bump `rlim_cur` toward `rlim_max`,
+/// capping at `kern.maxfilesperproc` on macOS since the kernel refuses a soft
+/// limit above it. The raise is best-effort: we never lower an already-higher
+/// limit and silently give up if the call fails.
+#[cfg(unix)]
+fn raise_nofile() {
+    let mut lim = libc::rlimit { rlim_cur: 0, rlim_max: 0 };
+    if unsafe { libc::getrlimit(libc::RLIMIT_NOFILE, &mut lim) } != 0 {
+        return;
+    }
+
+    let mut target = lim.rlim_max;
+    #[cfg(target_os = "macos")]
+    {
+        // macOS caps the per-process soft limit at kern.maxfilesperproc, so a
+        // setrlimit above it is rejected; clamp to it.
+        let mut maxproc: libc::c_int = 0;
+        let mut size = std::mem::size_of::<libc::c_int>();
+        let name = b"kern.maxfilesperproc\0";
+        let rc = unsafe {
+            libc::sysctlbyname(
+                name.as_ptr() as *const libc::c_char,
+                &mut maxproc as *mut _ as *mut libc::c_void,
+                &mut size,
+                std::ptr::null_mut(),
+                0,
+            )
+        };
+        if rc == 0 && maxproc > 0 {
+            target = target.min(maxproc as libc::rlim_t);
+        }
+    }
+
+    if target > lim.rlim_cur {
+        lim.rlim_cur = target;
+        unsafe { libc::setrlimit(libc::RLIMIT_NOFILE, &lim) };
+    }
+}
+
+/// Per-command scheduling state shared across the set, so dependents can wait
+/// for their prerequisites to either exit successfully or become healthy.
+struct NodeState {
+    healthy: AtomicBool,
+    finished: AtomicBool,
+    exit: std::sync::atomic::AtomicI32,
+}
+
+struct Schedule {
+    nodes: Vec<NodeState>,
+    /// Declared-name to index, for resolving `depends_on`.
+    by_name: std::collections::HashMap<String, usize>,
+    /// Woken whenever a node becomes healthy or finishes, so waiting dependents
+    /// re-check their prerequisites without busy-spinning.
+    notify: tokio::sync::Notify,
+}
+
+impl Schedule {
+    fn mark_healthy(&self, idx: usize) {
+        self.nodes[idx].healthy.store(true, Ordering::SeqCst);
+        self.notify.notify_waiters();
+    }
+
+    fn mark_finished(&self, idx: usize, code: i32) {
+        self.nodes[idx].exit.store(code, Ordering::SeqCst);
+        self.nodes[idx].finished.store(true, Ordering::SeqCst);
+        // A finished command also counts as "reached a terminal state" for any
+        // dependent still waiting on it.
+        self.notify.notify_waiters();
+    }
+
+    /// Await until every prerequisite of `spec` reaches the state its dependent
+    /// requires. Returns `Err(code)` if a prerequisite failed, so the dependent
+    /// is skipped and that failure propagates.
+    async fn wait_for_deps(&self, spec: &CommandSpec, cancel: &Arc<AtomicBool>) -> Result<(), i32> {
+        let targets: Vec<usize> = spec
+            .depends_on
+            .iter()
+            .filter_map(|d| self.by_name.get(d).copied())
+            .collect();
+        loop {
+            // Register interest before checking, so a notification that arrives
+            // mid-check is not lost.
+            let notified = self.notify.notified();
+            if cancel.load(Ordering::SeqCst) {
+                return Err(1);
+            }
+            match self.deps_state(&targets, spec.wait_for) {
+                Ok(true) => return Ok(()),
+                Ok(false) => {}
+                Err(code) => return Err(code),
+            }
+            tokio::select! {
+                _ = notified => {}
+                _ = tokio::time::sleep(Duration::from_millis(100)) => {}
+            }
+        }
+    }
+
+    /// `Ok(true)` if all prerequisites are ready, `Ok(false)` if still waiting,
+    /// `Err(code)` if one failed.
+    fn deps_state(&self, targets: &[usize], wait_for: WaitFor) -> Result<bool, i32> {
+        let mut all_ready = true;
+        for &t in targets {
+            let node = &self.nodes[t];
+            match wait_for {
+                WaitFor::Exit => {
+                    if node.finished.load(Ordering::SeqCst) {
+                        let code = node.exit.load(Ordering::SeqCst);
+                        if code != 0 {
+                            return Err(code);
+                        }
+                    } else {
+                        all_ready = false;
+                    }
+                }
+                WaitFor::Healthy => {
+                    if node.healthy.load(Ordering::SeqCst) {
+                        // ready
+                    } else if node.finished.load(Ordering::SeqCst) {
+                        // Exited before ever becoming healthy: treat as failure.
+                        let code = node.exit.load(Ordering::SeqCst);
+                        return Err(if code == 0 { 1 } else { code });
+                    } else {
+                        all_ready = false;
+                    }
+                }
+            }
+        }
+        Ok(all_ready)
+    }
+}
+
+/// Launch a set, honoring `depends_on`: commands with no unmet prerequisites
+/// start immediately, dependents start once their prerequisites have exited
+/// successfully (or become healthy). Returns the worst exit code once every
+/// command has finished. `cancel` lets a caller (watch mode) stop children early.
+///
+/// This is the synchronous entry point: it builds a tokio runtime (ghop already
+/// depends on tokio for the TUI) and drives the shared async core.
+fn run_set(commands: Vec<CommandSpec>, cancel: Arc<AtomicBool>) -> i32 {
+    let rt = tokio::runtime::Builder::new_multi_thread()
+        .enable_io()
+        .enable_time()
+        .build()
+        .expect("tokio runtime");
+    rt.block_on(run_set_async(commands, cancel))
+}
+
+async fn run_set_async(commands: Vec<CommandSpec>, cancel: Arc<AtomicBool>) -> i32 {
+    // Forward a Ctrl-C (or SIGTERM) received by ghop to every running group.
+    #[cfg(unix)]
+    procgroup::install_signal_forwarding();
+
+    let mut by_name = std::collections::HashMap::new();
+    let mut nodes = Vec::with_capacity(commands.len());
+    for (i, spec) in commands.iter().enumerate() {
+        if let Some(n) = &spec.name {
+            by_name.insert(n.clone(), i);
+        }
+        nodes.push(NodeState {
+            healthy: AtomicBool::new(false),
+            finished: AtomicBool::new(false),
+            exit: std::sync::atomic::AtomicI32::new(0),
+        });
+    }
+    let schedule = Arc::new(Schedule { nodes, by_name, notify: tokio::sync::Notify::new() });
+
     let print_lock = Arc::new(Mutex::new(()));
     let mut handles = Vec::with_capacity(commands.len());
     for (idx, spec) in commands.into_iter().enumerate() {
         let label = format!("{}", idx + 1);
         let print_lock = Arc::clone(&print_lock);
-        handles.push(thread::spawn(move || run_command(label, spec, print_lock)));
+        let cancel = Arc::clone(&cancel);
+        let schedule = Arc::clone(&schedule);
+        handles.push(tokio::spawn(run_scheduled(idx, label, spec, print_lock, cancel, schedule)));
     }
 
     // Collect exit codes and compute overall status
     let mut worst_code = 0;
     for h in handles {
-        match h.join() {
+        match h.await {
             Ok(code) => {
                 if code != 0 {
                     worst_code = code; // last non-zero code wins
@@ -128,3 +840,83 @@ pub fn run_commands(commands: Vec<CommandSpec>) -> i32 {
     }
     0
 }
+
+/// Wait for `spec`'s prerequisites, run it (wiring up any readiness probes), and
+/// publish its terminal state so dependents can proceed.
+async fn run_scheduled(
+    idx: usize,
+    label: String,
+    spec: CommandSpec,
+    print_lock: Arc<Mutex<()>>,
+    cancel: Arc<AtomicBool>,
+    schedule: Arc<Schedule>,
+) -> i32 {
+    if let Err(code) = schedule.wait_for_deps(&spec, &cancel).await {
+        {
+            let _g = print_lock.lock().unwrap();
+            eprintln!("[{label}][err] skipped: a prerequisite did not succeed");
+        }
+        schedule.mark_finished(idx, code);
+        return code;
+    }
+
+    // A `ready_port` probe flips the node healthy as soon as the port accepts.
+    if let Some(port) = spec.ready_port {
+        let schedule = Arc::clone(&schedule);
+        let cancel = Arc::clone(&cancel);
+        tokio::spawn(async move {
+            loop {
+                if cancel.load(Ordering::SeqCst) || schedule.nodes[idx].finished.load(Ordering::SeqCst) {
+                    return;
+                }
+                if tokio::net::TcpStream::connect(("127.0.0.1", port)).await.is_ok() {
+                    schedule.mark_healthy(idx);
+                    return;
+                }
+                tokio::time::sleep(Duration::from_millis(100)).await;
+            }
+        });
+    }
+
+    // A `ready_log` regex flips the node healthy on the first matching line.
+    let on_line: Option<LineHook> = match &spec.ready_log {
+        Some(pattern) => match regex::Regex::new(pattern) {
+            Ok(re) => {
+                let schedule = Arc::clone(&schedule);
+                Some(Arc::new(move |line: &str| {
+                    if !schedule.nodes[idx].healthy.load(Ordering::SeqCst) && re.is_match(line) {
+                        schedule.mark_healthy(idx);
+                    }
+                }) as LineHook)
+            }
+            Err(e) => {
+                let _g = print_lock.lock().unwrap();
+                eprintln!("[{label}][err] invalid ready_log regex: {e}");
+                None
+            }
+        },
+        None => None,
+    };
+
+    let code = run_command(label, spec, print_lock, cancel, on_line).await;
+    schedule.mark_finished(idx, code);
+    code
+}
+
+/// Run `commands` once, then keep ghop alive re-running the whole set whenever a
+/// watched file changes. `globs`, when non-empty, scopes which paths trigger a
+/// rerun; an empty list watches the whole working tree. This never returns under
+/// normal operation — the process lives until interrupted.
+pub fn run_commands_watched(commands: Vec<CommandSpec>, globs: Vec<String>) -> i32 {
+    crate::watch::watch_loop(commands, globs)
+}
+
+/// Spawn a set on a background thread that can be cancelled. Returns the join
+/// handle and the cancel flag used to signal it. Used by watch mode so the
+/// current run can be torn down before the next one starts.
+pub(crate) fn spawn_set(commands: Vec<CommandSpec>) -> (thread::JoinHandle<i32>, Arc<AtomicBool>) {
+    let cancel = Arc::new(AtomicBool::new(false));
+    let cancel_thread = Arc::clone(&cancel);
+    let handle = thread::spawn(move || run_set(commands, cancel_thread));
+    (handle, cancel)
+}
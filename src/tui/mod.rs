@@ -2,68 +2,329 @@ use anyhow::Result;
 use ratatui::{prelude::*, widgets::*};
 use crossterm::{event, execute, terminal};
 use crossterm::event::{Event, KeyCode, KeyModifiers};
+#[cfg(windows)]
 use tokio::io::{AsyncBufReadExt, BufReader as AsyncBufReader};
 use tokio::process::Child;
 use tokio::sync::{mpsc, watch};
 
 const DEFAULT_EXIT_CODE: i32 = -1;
 
+// How long a child is given to exit on its own after SIGTERM before it is
+// forcibly killed.
+const SHUTDOWN_GRACE: std::time::Duration = std::time::Duration::from_secs(5);
 
+// Extra scrollback kept above the visible pane so repainting programs have room
+// and users are not limited to exactly one screenful.
+const SCROLLBACK: usize = 1000;
+
+// Minimum delay between a process exiting and its relaunch in restart mode, so
+// a command that dies instantly does not spin in a tight respawn loop.
+const RESTART_DELAY: std::time::Duration = std::time::Duration::from_millis(250);
+
+// Raw output from a command. A PTY merges stdout and stderr, so `bytes` may
+// carry arbitrary ANSI escape sequences; they are handed to a vt100 parser
+// rather than stored as text.
+#[derive(Debug)]
+struct OutputMsg { idx: usize, bytes: Vec<u8> }
+
+// Lifecycle of one command, reported by its supervisor as it runs, exits, and
+// (in restart mode) waits to be relaunched. `Killed` distinguishes a process
+// torn down by a signal (or our own shutdown) from a normal `Exited`.
 #[derive(Clone, Copy, Debug)]
-enum StreamKind { Stdout, Stderr }
+enum RunState { Running, Exited(i32), Killed, Restarting }
 
 #[derive(Debug)]
-struct LineMsg { idx: usize, kind: StreamKind, text: String }
+struct StatusMsg { idx: usize, runs: u64, state: RunState }
+
+// How a single run ended: its exit code, and whether it was killed by a signal
+// rather than exiting on its own.
+struct RunOutcome { code: i32, killed: bool }
+
+// Per-command view state tracked by the draw loop. `started`/`ended` bound the
+// wall-clock runtime shown in the status bar.
+struct CmdState {
+    runs: u64,
+    state: RunState,
+    started: Option<std::time::Instant>,
+    ended: Option<std::time::Instant>,
+}
 
 struct App {
-    logs: Vec<Vec<String>>, // one buffer per command
+    screens: Vec<vt100::Parser>, // one terminal emulator per command
+    states: Vec<CmdState>,
+    // Per-pane scrollback offset (rows above the bottom) and whether the pane
+    // tails new output. `follow` turns off when the user scrolls up.
+    offset: Vec<usize>,
+    follow: Vec<bool>,
     selected: usize,
+    // Some while the user is typing a `/` search; holds the in-progress query.
+    searching: Option<String>,
+    // Last committed query, reused by `n` to search further back.
+    query: Option<String>,
 }
 
 impl App {
-    fn new(n: usize) -> Self { Self { logs: vec![Vec::new(); n], selected: 0 } }
-    fn push(&mut self, msg: LineMsg) {
-        let buf = &mut self.logs[msg.idx];
-        if buf.len() > 10_000 { buf.drain(..5_000); }
-        let prefix = match msg.kind { StreamKind::Stdout => "", StreamKind::Stderr => "[err] " };
-        buf.push(format!("{}{}", prefix, msg.text));
+    fn new(n: usize, rows: u16, cols: u16) -> Self {
+        let screens = (0..n)
+            .map(|_| vt100::Parser::new(rows, cols, SCROLLBACK))
+            .collect();
+        let states = (0..n)
+            .map(|_| CmdState { runs: 0, state: RunState::Running, started: None, ended: None })
+            .collect();
+        Self {
+            screens,
+            states,
+            offset: vec![0; n],
+            follow: vec![true; n],
+            selected: 0,
+            searching: None,
+            query: None,
+        }
+    }
+
+    /// Push the selected pane's scroll position into its vt100 emulator. When
+    /// following, the view is pinned to the live bottom of the stream.
+    fn apply_scroll(&mut self) {
+        let sel = self.selected;
+        let off = if self.follow[sel] { 0 } else { self.offset[sel] };
+        self.screens[sel].set_scrollback(off);
+        self.offset[sel] = off;
+    }
+
+    fn scroll_up(&mut self, n: usize) {
+        let sel = self.selected;
+        self.offset[sel] = (self.offset[sel] + n).min(SCROLLBACK);
+        self.follow[sel] = false;
+    }
+
+    fn scroll_down(&mut self, n: usize) {
+        let sel = self.selected;
+        self.offset[sel] = self.offset[sel].saturating_sub(n);
+        if self.offset[sel] == 0 {
+            self.follow[sel] = true;
+        }
+    }
+
+    fn scroll_top(&mut self) {
+        self.offset[self.selected] = SCROLLBACK;
+        self.follow[self.selected] = false;
+    }
+
+    fn scroll_bottom(&mut self) {
+        let sel = self.selected;
+        self.offset[sel] = 0;
+        self.follow[sel] = true;
+    }
+
+    /// Scroll the selected pane to the first screenful containing `query` at or
+    /// above `start`, scanning older rows. Restores the current view and
+    /// returns `false` if nothing matches.
+    fn find(&mut self, query: &str, start: usize) -> bool {
+        if query.is_empty() { return false; }
+        let sel = self.selected;
+        for off in start..=SCROLLBACK {
+            self.screens[sel].set_scrollback(off);
+            if self.screens[sel].screen().contents().contains(query) {
+                self.offset[sel] = off;
+                self.follow[sel] = false;
+                return true;
+            }
+        }
+        self.screens[sel].set_scrollback(self.offset[sel]);
+        false
+    }
+
+    /// Live search while typing: jump to the most recent (closest to the
+    /// bottom) match so the view tracks the query as it is refined.
+    fn search_incremental(&mut self, query: &str) {
+        self.find(query, 0);
+    }
+
+    /// Repeat a committed search with `n`: scroll to the next screenful, at least
+    /// one row older than the current view, that contains `query`. While the
+    /// current match is still on screen that is just one row up, so the same
+    /// match can stay in view across several presses.
+    fn search_next(&mut self, query: &str) {
+        let start = self.offset[self.selected] + 1;
+        self.find(query, start);
+    }
+    fn push(&mut self, msg: OutputMsg) {
+        self.screens[msg.idx].process(&msg.bytes);
+    }
+    fn status(&mut self, msg: StatusMsg) {
+        let now = std::time::Instant::now();
+        // A new run delimits the pane so the previous run's tail stays in
+        // scrollback but the boundary is obvious, and restarts the clock.
+        if let RunState::Running = msg.state {
+            if msg.runs > 0 {
+                let banner = format!("\r\n\x1b[7m──── run {} ────\x1b[0m\r\n", msg.runs + 1);
+                self.screens[msg.idx].process(banner.as_bytes());
+            }
+        }
+        let st = &mut self.states[msg.idx];
+        st.runs = msg.runs;
+        st.state = msg.state;
+        match msg.state {
+            RunState::Running => { st.started = Some(now); st.ended = None; }
+            RunState::Exited(_) | RunState::Killed => { st.ended = Some(now); }
+            RunState::Restarting => {}
+        }
+    }
+    fn set_size(&mut self, rows: u16, cols: u16) {
+        for p in &mut self.screens {
+            p.set_size(rows, cols);
+        }
+    }
+}
+
+/// Translate a vt100 color into the ratatui equivalent, mapping the terminal
+/// default onto `Reset` so the pane inherits the surrounding theme.
+fn vt_color(c: vt100::Color) -> Color {
+    match c {
+        vt100::Color::Default => Color::Reset,
+        vt100::Color::Idx(i) => Color::Indexed(i),
+        vt100::Color::Rgb(r, g, b) => Color::Rgb(r, g, b),
+    }
+}
+
+/// Build the ratatui style for a single vt100 cell.
+fn cell_style(cell: &vt100::Cell) -> Style {
+    let mut modifier = Modifier::empty();
+    if cell.bold() { modifier |= Modifier::BOLD; }
+    if cell.italic() { modifier |= Modifier::ITALIC; }
+    if cell.underline() { modifier |= Modifier::UNDERLINED; }
+    if cell.inverse() { modifier |= Modifier::REVERSED; }
+    Style::default()
+        .fg(vt_color(cell.fgcolor()))
+        .bg(vt_color(cell.bgcolor()))
+        .add_modifier(modifier)
+}
+
+/// Colour used for a command's status dot in tabs and the status bar.
+fn state_color(state: &RunState) -> Color {
+    match state {
+        RunState::Running => Color::Green,
+        RunState::Exited(0) => Color::Gray,
+        RunState::Exited(_) | RunState::Killed => Color::Red,
+        RunState::Restarting => Color::Yellow,
     }
 }
 
+/// Compact, human-readable runtime (e.g. `2.3s`, `1m04s`).
+fn fmt_elapsed(d: std::time::Duration) -> String {
+    let secs = d.as_secs();
+    if secs >= 60 {
+        format!("{}m{:02}s", secs / 60, secs % 60)
+    } else {
+        format!("{}.{}s", secs, d.subsec_millis() / 100)
+    }
+}
+
+/// Walk a vt100 screen row by row, coalescing runs of equally-styled cells into
+/// `Span`s so repaints (progress bars, spinners) render as the program drew
+/// them rather than as appended text. Rows containing the active search
+/// `highlight` are rebuilt with the matched substrings styled so the match
+/// stays visible wherever it sits in the buffer.
+fn screen_lines(screen: &vt100::Screen, highlight: Option<&str>) -> Vec<Line<'static>> {
+    let (rows, cols) = screen.size();
+    let query = highlight.filter(|q| !q.is_empty());
+    let mut lines = Vec::with_capacity(rows as usize);
+    for row in 0..rows {
+        // Plain row text, used both for matching and for the highlight rebuild.
+        let mut plain = String::with_capacity(cols as usize);
+        for col in 0..cols {
+            match screen.cell(row, col) {
+                Some(cell) if !cell.contents().is_empty() => plain.push_str(&cell.contents()),
+                _ => plain.push(' '),
+            }
+        }
+        if let Some(q) = query {
+            if plain.contains(q) {
+                lines.push(highlight_line(&plain, q));
+                continue;
+            }
+        }
+
+        let mut spans: Vec<Span> = Vec::new();
+        let mut run = String::new();
+        let mut run_style = Style::default();
+        for col in 0..cols {
+            let (text, style) = match screen.cell(row, col) {
+                Some(cell) => {
+                    let contents = cell.contents();
+                    let text = if contents.is_empty() { " ".to_string() } else { contents };
+                    (text, cell_style(cell))
+                }
+                None => (" ".to_string(), Style::default()),
+            };
+            if !run.is_empty() && style != run_style {
+                spans.push(Span::styled(std::mem::take(&mut run), run_style));
+            }
+            run_style = style;
+            run.push_str(&text);
+        }
+        if !run.is_empty() {
+            spans.push(Span::styled(run, run_style));
+        }
+        lines.push(Line::from(spans));
+    }
+    lines
+}
+
+/// Render one row with every occurrence of `q` styled as a search hit.
+fn highlight_line(text: &str, q: &str) -> Line<'static> {
+    let hit = Style::default().fg(Color::Black).bg(Color::Yellow).add_modifier(Modifier::BOLD);
+    let mut spans: Vec<Span> = Vec::new();
+    let mut rest = text;
+    while let Some(pos) = rest.find(q) {
+        if pos > 0 {
+            spans.push(Span::raw(rest[..pos].to_string()));
+        }
+        spans.push(Span::styled(q.to_string(), hit));
+        rest = &rest[pos + q.len()..];
+    }
+    if !rest.is_empty() {
+        spans.push(Span::raw(rest.to_string()));
+    }
+    Line::from(spans)
+}
+
+// Without a PTY (Windows) stdout and stderr arrive as separate line streams;
+// re-synthesize CRLF-terminated lines so they feed the vt100 parser the same
+// way a terminal's combined stream would, tagging stderr with an `[err]`
+// prefix since the two streams can no longer be distinguished downstream.
+#[cfg(windows)]
 async fn forward_lines<R>(
     mut lines: tokio::io::Lines<tokio::io::BufReader<R>>,
     idx: usize,
-    kind: StreamKind,
-    tx: mpsc::Sender<LineMsg>,
+    is_err: bool,
+    tx: mpsc::Sender<OutputMsg>,
 ) where
     R: tokio::io::AsyncRead + Unpin,
 {
     while let Ok(Some(text)) = lines.next_line().await {
+        let line = if is_err { format!("[err] {text}\r\n") } else { format!("{text}\r\n") };
         // Ignore send errors (receiver might have been dropped)
-        let _ = tx.send(make_line_msg(idx, kind, text)).await;
+        let _ = tx.send(OutputMsg { idx, bytes: line.into_bytes() }).await;
     }
 }
 
-#[inline]
-fn make_line_msg(idx: usize, kind: StreamKind, text: String) -> LineMsg {
-    LineMsg { idx, kind, text }
-}
-
 // Reads child's stdout/stderr lines, forwards them via tx, and returns exit code.
+#[cfg(windows)]
 async fn spawn_reader(
     mut child: Child,
     idx: usize,
-    tx: mpsc::Sender<LineMsg>,
+    tx: mpsc::Sender<OutputMsg>,
     mut cancel_rx: watch::Receiver<bool>,
-) -> Result<i32> {
+) -> Result<RunOutcome> {
     // Gracefully handle missing stdio instead of panicking
     let stdout = match child.stdout.take() {
         Some(s) => s,
-        None => return Ok(DEFAULT_EXIT_CODE),
+        None => return Ok(RunOutcome { code: DEFAULT_EXIT_CODE, killed: false }),
     };
     let stderr = match child.stderr.take() {
         Some(s) => s,
-        None => return Ok(DEFAULT_EXIT_CODE),
+        None => return Ok(RunOutcome { code: DEFAULT_EXIT_CODE, killed: false }),
     };
 
     let out_reader = AsyncBufReader::new(stdout).lines();
@@ -73,13 +334,13 @@ async fn spawn_reader(
     let stdout_task = {
         let tx_out = tx.clone();
         tokio::spawn(async move {
-            forward_lines(out_reader, idx, StreamKind::Stdout, tx_out).await;
+            forward_lines(out_reader, idx, false, tx_out).await;
         })
     };
     let stderr_task = {
         let tx_err = tx.clone();
         tokio::spawn(async move {
-            forward_lines(err_reader, idx, StreamKind::Stderr, tx_err).await;
+            forward_lines(err_reader, idx, true, tx_err).await;
         })
     };
 
@@ -87,9 +348,15 @@ async fn spawn_reader(
     let status = tokio::select! {
         res = child.wait() => res?,
         _ = cancel_rx.changed() => {
-            // Best-effort terminate and wait
-            let _ = child.kill().await;
-            child.wait().await?
+            // Windows has no SIGTERM; give the child the grace window anyway in
+            // case it is already exiting, then terminate it as a last resort.
+            match tokio::time::timeout(SHUTDOWN_GRACE, child.wait()).await {
+                Ok(res) => res?,
+                Err(_) => {
+                    let _ = child.kill().await;
+                    child.wait().await?
+                }
+            }
         }
     };
 
@@ -97,13 +364,343 @@ async fn spawn_reader(
     let _ = stdout_task.await;
     let _ = stderr_task.await;
 
-    Ok(status.code().unwrap_or(DEFAULT_EXIT_CODE))
+    Ok(outcome(status))
+}
+
+/// PTY-backed spawning for the TUI: each command runs attached to its own
+/// pseudo-terminal so it believes it is talking to an interactive terminal and
+/// keeps colors, progress bars, and spinners. A PTY exposes a single combined
+/// output stream, so stdout and stderr are merged and read as raw bytes from
+/// the master fd rather than through line-oriented pipes.
+#[cfg(unix)]
+mod pty {
+    use super::*;
+    use std::os::unix::io::{FromRawFd, RawFd};
+    use std::os::unix::process::CommandExt;
+
+    /// Allocate a PTY pair sized to `ws`, spawn `cmd` attached to the slave, and
+    /// return the child plus the master fd (for reading output and resizing).
+    pub(super) fn spawn(cmd: &str, ws: libc::winsize) -> Result<(Child, RawFd)> {
+        let mut master: RawFd = -1;
+        let mut slave: RawFd = -1;
+        let rc = unsafe {
+            libc::openpty(&mut master, &mut slave, std::ptr::null_mut(), std::ptr::null(), &ws)
+        };
+        if rc != 0 {
+            anyhow::bail!("failed to allocate pty");
+        }
+
+        let mut command = std::process::Command::new("sh");
+        command.arg("-c").arg(cmd);
+        // The child becomes its own session leader with the slave as its
+        // controlling terminal; stdin/stdout/stderr all point at the slave.
+        unsafe {
+            command.pre_exec(move || {
+                if libc::setsid() == -1 {
+                    return Err(std::io::Error::last_os_error());
+                }
+                if libc::ioctl(slave, libc::TIOCSCTTY as _, 0) == -1 {
+                    return Err(std::io::Error::last_os_error());
+                }
+                for target in [libc::STDIN_FILENO, libc::STDOUT_FILENO, libc::STDERR_FILENO] {
+                    if libc::dup2(slave, target) == -1 {
+                        return Err(std::io::Error::last_os_error());
+                    }
+                }
+                if slave > libc::STDERR_FILENO {
+                    libc::close(slave);
+                }
+                libc::close(master);
+                Ok(())
+            });
+        }
+
+        let mut command = tokio::process::Command::from(command);
+        command.kill_on_drop(true);
+        let child = match command.spawn() {
+            Ok(c) => c,
+            Err(e) => {
+                unsafe {
+                    libc::close(master);
+                    libc::close(slave);
+                }
+                return Err(e.into());
+            }
+        };
+        // The slave belongs to the child now.
+        unsafe { libc::close(slave) };
+        Ok((child, master))
+    }
+
+    /// Wrap the master fd as an async file for reading the combined stream.
+    pub(super) fn master_reader(master: RawFd) -> tokio::fs::File {
+        tokio::fs::File::from_std(unsafe { std::fs::File::from_raw_fd(master) })
+    }
+
+    /// Read raw bytes from the PTY master and forward them unparsed, escape
+    /// sequences included, for the pane's vt100 emulator to interpret.
+    pub(super) async fn forward(mut master: tokio::fs::File, idx: usize, tx: mpsc::Sender<OutputMsg>) {
+        use tokio::io::AsyncReadExt;
+        let mut buf = [0u8; 4096];
+        loop {
+            match master.read(&mut buf).await {
+                Ok(0) => break,
+                // A closed PTY master reads as EIO; treat that as end-of-stream.
+                Err(_) => break,
+                Ok(n) => {
+                    let _ = tx.send(OutputMsg { idx, bytes: buf[..n].to_vec() }).await;
+                }
+            }
+        }
+    }
+
+    /// Send `sig` to the child's whole process group. Each PTY child calls
+    /// `setsid` in `pre_exec`, so it leads its own group and its grandchildren
+    /// (e.g. a dev server's node process) receive the signal too.
+    pub(super) fn signal_group(child: &Child, sig: libc::c_int) {
+        if let Some(pid) = child.id() {
+            unsafe { libc::killpg(pid as libc::pid_t, sig) };
+        }
+    }
+
+    /// Push the output-pane size down to a live PTY so full-screen children
+    /// (and progress bars) lay themselves out to the visible area.
+    pub(super) fn resize(master: RawFd, ws: &libc::winsize) {
+        unsafe { libc::ioctl(master, libc::TIOCSWINSZ, ws) };
+    }
+
+    /// Size a PTY to the output pane: the terminal minus the tab bar, the help
+    /// line, and the output block's own borders.
+    pub(super) fn pane_winsize(cols: u16, rows: u16) -> libc::winsize {
+        let (ws_row, ws_col) = super::pane_dims(cols, rows);
+        libc::winsize { ws_row, ws_col, ws_xpixel: 0, ws_ypixel: 0 }
+    }
+}
+
+/// Read the combined PTY stream for one pane until the child exits or the run
+/// is cancelled, returning the child's exit code.
+#[cfg(unix)]
+async fn spawn_reader_pty(
+    mut child: Child,
+    master: std::os::unix::io::RawFd,
+    idx: usize,
+    tx: mpsc::Sender<OutputMsg>,
+    mut cancel_rx: watch::Receiver<bool>,
+) -> Result<RunOutcome> {
+    let reader = tokio::spawn(pty::forward(pty::master_reader(master), idx, tx));
+    let status = tokio::select! {
+        res = child.wait() => res?,
+        _ = cancel_rx.changed() => {
+            // Phase one: ask the whole group to terminate and give it a grace
+            // period to clean up; phase two: escalate to SIGKILL if it lingers.
+            pty::signal_group(&child, libc::SIGTERM);
+            match tokio::time::timeout(SHUTDOWN_GRACE, child.wait()).await {
+                Ok(res) => res?,
+                Err(_) => {
+                    pty::signal_group(&child, libc::SIGKILL);
+                    child.wait().await?
+                }
+            }
+        }
+    };
+    let _ = reader.await;
+    Ok(outcome(status))
+}
+
+/// Classify an exit status: a process with no exit code was terminated by a
+/// signal (Unix) and is reported as `Killed`.
+fn outcome(status: std::process::ExitStatus) -> RunOutcome {
+    match status.code() {
+        Some(code) => RunOutcome { code, killed: false },
+        None => RunOutcome { code: DEFAULT_EXIT_CODE, killed: true },
+    }
+}
+
+/// Interior size of the output pane given the full terminal dimensions: the
+/// terminal minus the tab bar, the help line, and the output block's borders.
+/// Shared by the vt100 emulators and (on Unix) the PTY winsize so both agree.
+fn pane_dims(cols: u16, rows: u16) -> (u16, u16) {
+    (rows.saturating_sub(4).max(1), cols.saturating_sub(2).max(1))
+}
+
+/// Milliseconds since the Unix epoch, used to timestamp log records.
+fn now_millis() -> u128 {
+    std::time::SystemTime::now()
+        .duration_since(std::time::UNIX_EPOCH)
+        .map(|d| d.as_millis())
+        .unwrap_or(0)
+}
+
+/// Drop ANSI escape sequences so on-disk logs are plain text even though the
+/// live panes render color. Handles CSI (`ESC [ … letter`) and OSC
+/// (`ESC ] … BEL`) sequences, the two a terminal program emits in practice.
+fn strip_ansi(s: &str) -> String {
+    let mut out = String::with_capacity(s.len());
+    let mut chars = s.chars().peekable();
+    while let Some(c) = chars.next() {
+        if c != '\x1b' {
+            out.push(c);
+            continue;
+        }
+        match chars.peek() {
+            Some('[') => {
+                chars.next();
+                while let Some(c2) = chars.next() {
+                    if c2.is_ascii_alphabetic() { break; }
+                }
+            }
+            Some(']') => {
+                chars.next();
+                for c2 in chars.by_ref() {
+                    if c2 == '\x07' { break; }
+                }
+            }
+            _ => { chars.next(); }
+        }
+    }
+    out
+}
+
+/// Sanitize a command string into a filesystem-safe log file stem.
+fn log_stem(cmd: &str) -> String {
+    let stem: String = cmd
+        .chars()
+        .map(|c| if c.is_ascii_alphanumeric() { c } else { '_' })
+        .take(40)
+        .collect();
+    stem.trim_matches('_').to_string()
+}
+
+/// Tees command output to disk independently of the in-memory ring buffer: a
+/// combined interleaved log plus one file per command, so a long parallel run
+/// stays auditable after the TUI scrollback has rotated. Lines are stripped of
+/// ANSI and stamped with an epoch-millisecond timestamp.
+struct LogWriter {
+    combined: std::fs::File,
+    per_cmd: Vec<std::fs::File>,
+    pending: Vec<Vec<u8>>,
+}
+
+impl LogWriter {
+    fn new(dir: &std::path::Path, commands: &[String]) -> Result<Self> {
+        std::fs::create_dir_all(dir)?;
+        let combined = std::fs::File::create(dir.join("combined.log"))?;
+        let mut per_cmd = Vec::with_capacity(commands.len());
+        for (i, c) in commands.iter().enumerate() {
+            per_cmd.push(std::fs::File::create(dir.join(format!("{i}-{}.log", log_stem(c))))?);
+        }
+        Ok(Self { combined, per_cmd, pending: vec![Vec::new(); commands.len()] })
+    }
+
+    fn write(&mut self, idx: usize, bytes: &[u8]) {
+        use std::io::Write;
+        let buf = &mut self.pending[idx];
+        buf.extend_from_slice(bytes);
+        while let Some(pos) = buf.iter().position(|&b| b == b'\n') {
+            let mut line: Vec<u8> = buf.drain(..=pos).collect();
+            line.pop();
+            if line.last() == Some(&b'\r') { line.pop(); }
+            let text = strip_ansi(&String::from_utf8_lossy(&line));
+            let ts = now_millis();
+            let _ = writeln!(self.per_cmd[idx], "{ts} [out] {text}");
+            let _ = writeln!(self.combined, "{ts} [cmd{idx}] {text}");
+        }
+    }
+}
+
+/// Supervise one command: run it, report its lifecycle on `stx`, and in restart
+/// mode relaunch it after it exits (debounced by `RESTART_DELAY`). Returns the
+/// last observed exit code. On Unix the live PTY master is published into
+/// `masters[idx]` so the draw loop can resize it, and cleared while it is down.
+#[cfg(unix)]
+async fn supervise(
+    idx: usize,
+    cmd: String,
+    restart: bool,
+    tx: mpsc::Sender<OutputMsg>,
+    stx: mpsc::Sender<StatusMsg>,
+    mut cancel_rx: watch::Receiver<bool>,
+    masters: std::sync::Arc<std::sync::Mutex<Vec<Option<std::os::unix::io::RawFd>>>>,
+) -> i32 {
+    let mut runs = 0u64;
+    let mut last_code = DEFAULT_EXIT_CODE;
+    loop {
+        let (cols, rows) = terminal::size().unwrap_or((80, 24));
+        let (child, master) = match pty::spawn(&cmd, pty::pane_winsize(cols, rows)) {
+            Ok(v) => v,
+            Err(_) => break,
+        };
+        masters.lock().unwrap()[idx] = Some(master);
+        let _ = stx.send(StatusMsg { idx, runs, state: RunState::Running }).await;
+        let oc = spawn_reader_pty(child, master, idx, tx.clone(), cancel_rx.clone())
+            .await
+            .unwrap_or(RunOutcome { code: DEFAULT_EXIT_CODE, killed: false });
+        masters.lock().unwrap()[idx] = None;
+        last_code = oc.code;
+        let state = if oc.killed { RunState::Killed } else { RunState::Exited(oc.code) };
+        let _ = stx.send(StatusMsg { idx, runs, state }).await;
+
+        if !restart || *cancel_rx.borrow() { break; }
+        let _ = stx.send(StatusMsg { idx, runs, state: RunState::Restarting }).await;
+        tokio::select! {
+            _ = tokio::time::sleep(RESTART_DELAY) => {}
+            _ = cancel_rx.changed() => break,
+        }
+        runs += 1;
+    }
+    last_code
 }
 
-pub async fn run(commands: Vec<String>) -> Result<i32> {
+#[cfg(windows)]
+async fn supervise(
+    idx: usize,
+    cmd: String,
+    restart: bool,
+    tx: mpsc::Sender<OutputMsg>,
+    stx: mpsc::Sender<StatusMsg>,
+    mut cancel_rx: watch::Receiver<bool>,
+) -> i32 {
+    let mut runs = 0u64;
+    let mut last_code = DEFAULT_EXIT_CODE;
+    loop {
+        let child = match tokio::process::Command::new("cmd").arg("/C").arg(&cmd)
+            .stdout(std::process::Stdio::piped())
+            .stderr(std::process::Stdio::piped())
+            .spawn()
+        {
+            Ok(c) => c,
+            Err(_) => break,
+        };
+        let _ = stx.send(StatusMsg { idx, runs, state: RunState::Running }).await;
+        let oc = spawn_reader(child, idx, tx.clone(), cancel_rx.clone())
+            .await
+            .unwrap_or(RunOutcome { code: DEFAULT_EXIT_CODE, killed: false });
+        last_code = oc.code;
+        let state = if oc.killed { RunState::Killed } else { RunState::Exited(oc.code) };
+        let _ = stx.send(StatusMsg { idx, runs, state }).await;
+
+        if !restart || *cancel_rx.borrow() { break; }
+        let _ = stx.send(StatusMsg { idx, runs, state: RunState::Restarting }).await;
+        tokio::select! {
+            _ = tokio::time::sleep(RESTART_DELAY) => {}
+            _ = cancel_rx.changed() => break,
+        }
+        runs += 1;
+    }
+    last_code
+}
+
+pub async fn run(commands: Vec<String>, restart: bool, log_dir: Option<std::path::PathBuf>) -> Result<i32> {
     use std::io;
     use std::time::Duration;
 
+    // Optional durable tee of all output, opened before the terminal is taken
+    // over so a failure to create the log files surfaces as a plain error.
+    let mut logger = match log_dir {
+        Some(dir) => Some(LogWriter::new(&dir, &commands)?),
+        None => None,
+    };
+
     // terminal setup
     terminal::enable_raw_mode()?;
     let mut stdout = io::stdout();
@@ -112,34 +709,67 @@ pub async fn run(commands: Vec<String>) -> Result<i32> {
     let mut terminal = Terminal::new(backend)?;
 
     // channels
-    let (tx, mut rx) = tokio::sync::mpsc::channel::<LineMsg>(1024);
+    let (tx, mut rx) = tokio::sync::mpsc::channel::<OutputMsg>(1024);
+    // per-command lifecycle updates (running / exited / restarting)
+    let (stx, mut srx) = tokio::sync::mpsc::channel::<StatusMsg>(256);
     // cancellation signal for spawned processes
     let (cancel_tx, cancel_rx) = tokio::sync::watch::channel::<bool>(false);
 
-    // spawn processes
+    // Master fds of every live PTY, so a terminal resize can be propagated.
+    // A command is down between restarts, so each slot is an `Option`.
+    #[cfg(unix)]
+    let masters = std::sync::Arc::new(std::sync::Mutex::new(vec![None; commands.len()]));
+
+    // spawn a supervisor per command
     let mut join_handles = Vec::new();
     for (idx, cmd) in commands.iter().enumerate() {
-        #[cfg(windows)]
-        let child = tokio::process::Command::new("cmd").arg("/C").arg(cmd)
-            .stdout(std::process::Stdio::piped())
-            .stderr(std::process::Stdio::piped())
-            .spawn()?;
-        #[cfg(not(windows))]
-        let child = tokio::process::Command::new("sh").arg("-c").arg(cmd)
-            .stdout(std::process::Stdio::piped())
-            .stderr(std::process::Stdio::piped())
-            .spawn()?;
         let txc = tx.clone();
+        let stxc = stx.clone();
         let crx = cancel_rx.clone();
-        join_handles.push(tokio::spawn(spawn_reader(child, idx, txc, crx)));
+        let cmd = cmd.clone();
+        #[cfg(unix)]
+        {
+            let masters = masters.clone();
+            join_handles.push(tokio::spawn(supervise(idx, cmd, restart, txc, stxc, crx, masters)));
+        }
+        #[cfg(windows)]
+        {
+            join_handles.push(tokio::spawn(supervise(idx, cmd, restart, txc, stxc, crx)));
+        }
     }
     drop(tx);
+    drop(stx);
 
-    let mut app = App::new(commands.len());
+    let (init_cols, init_rows) = terminal::size().unwrap_or((80, 24));
+    let (pane_rows, pane_cols) = pane_dims(init_cols, init_rows);
+    let mut app = App::new(commands.len(), pane_rows, pane_cols);
+
+    // A SIGINT/SIGTERM delivered to ghop itself (e.g. Ctrl-C from a parent
+    // shell, or a `kill`) routes into the same teardown path as pressing `q`,
+    // so every child is signalled and drained instead of being orphaned.
+    use std::sync::atomic::{AtomicBool, Ordering};
+    let os_shutdown = std::sync::Arc::new(AtomicBool::new(false));
+    #[cfg(unix)]
+    {
+        use tokio::signal::unix::{signal, SignalKind};
+        let flag = os_shutdown.clone();
+        let mut sigint = signal(SignalKind::interrupt())?;
+        let mut sigterm = signal(SignalKind::terminate())?;
+        tokio::spawn(async move {
+            tokio::select! {
+                _ = sigint.recv() => {}
+                _ = sigterm.recv() => {}
+            }
+            flag.store(true, Ordering::SeqCst);
+        });
+    }
 
     // main loop
     loop {
+        if os_shutdown.load(Ordering::SeqCst) { break; }
+
         // draw
+        app.apply_scroll();
         terminal.draw(|f| {
             let chunks = Layout::default()
                 .direction(Direction::Vertical)
@@ -150,41 +780,136 @@ pub async fn run(commands: Vec<String>) -> Result<i32> {
                 ]).split(f.area());
 
             let titles: Vec<Line> = commands.iter().enumerate().map(|(i, c)| {
-                let title = format!("{}: {}", i+1, c);
-                Line::from(Span::styled(title, Style::default().fg(if i==app.selected { Color::Yellow } else { Color::White })))
+                let s = &app.states[i];
+                let run_n = s.runs + 1;
+                let badge = match s.state {
+                    RunState::Running => format!("▶ #{run_n}"),
+                    RunState::Exited(code) => format!("⏹ #{run_n} ({code})"),
+                    RunState::Killed => format!("✗ #{run_n}"),
+                    RunState::Restarting => format!("↻ #{run_n}"),
+                };
+                let fg = if i == app.selected { Color::Yellow } else { Color::White };
+                Line::from(vec![
+                    Span::styled("● ", Style::default().fg(state_color(&s.state))),
+                    Span::styled(format!("{}: {} {}", i+1, c, badge), Style::default().fg(fg)),
+                ])
             }).collect();
             let tabs = Tabs::new(titles).select(app.selected);
             f.render_widget(tabs, chunks[0]);
 
-            let items: Vec<ListItem> = app.logs[app.selected]
-                .iter().rev().take(1000).rev()
-                .map(|l| ListItem::new(l.as_str()))
-                .collect();
-            let list = List::new(items).block(Block::default().title("Output").borders(Borders::ALL));
-            f.render_widget(list, chunks[1]);
+            let lines = screen_lines(app.screens[app.selected].screen(), app.query.as_deref());
+            let output = Paragraph::new(lines)
+                .block(Block::default().title("Output").borders(Borders::ALL));
+            f.render_widget(output, chunks[1]);
 
-            let help = Paragraph::new("q=quit  ←/→=pane  Tab=next  Shift-Tab=prev");
+            // Bottom bar: live status of the selected pane, then the keymap.
+            let sel = &app.states[app.selected];
+            let elapsed = match (sel.started, sel.ended) {
+                (Some(s), Some(e)) => Some(e.duration_since(s)),
+                (Some(s), None) => Some(s.elapsed()),
+                _ => None,
+            };
+            let el = elapsed.map(fmt_elapsed).unwrap_or_default();
+            let status_txt = match sel.state {
+                RunState::Running => format!("running {el}"),
+                RunState::Exited(code) => format!("exited {code} in {el}"),
+                RunState::Killed => format!("killed after {el}"),
+                RunState::Restarting => "restarting".to_string(),
+            };
+            let help = if let Some(buf) = &app.searching {
+                // Search-entry line takes over the bar while typing a query.
+                Paragraph::new(Line::from(vec![
+                    Span::styled("/", Style::default().fg(Color::Cyan)),
+                    Span::raw(buf.clone()),
+                    Span::styled("  (Enter to jump, Esc to cancel)", Style::default().fg(Color::DarkGray)),
+                ]))
+            } else {
+                let scroll = if app.follow[app.selected] {
+                    "FOLLOW".to_string()
+                } else {
+                    format!("SCROLL +{}", app.offset[app.selected])
+                };
+                Paragraph::new(Line::from(vec![
+                    Span::styled("● ", Style::default().fg(state_color(&sel.state))),
+                    Span::raw(format!("{status_txt}  ")),
+                    Span::styled(format!("[{scroll}]  "), Style::default().fg(Color::Cyan)),
+                    Span::styled("q=quit  ←/→=pane  PgUp/PgDn=scroll  Home/End  /=search  n=next", Style::default().fg(Color::DarkGray)),
+                ]))
+            };
             f.render_widget(help, chunks[2]);
         })?;
 
         // drain new lines with a short timeout
         let mut drained = 0;
         while let Ok(Some(msg)) = tokio::time::timeout(Duration::from_millis(1), rx.recv()).await {
+            if let Some(l) = logger.as_mut() { l.write(msg.idx, &msg.bytes); }
             app.push(msg);
             drained += 1;
             if drained > 10_000 { break; }
         }
 
+        // fold in any lifecycle changes (running / exited / restarting)
+        while let Ok(msg) = srx.try_recv() {
+            app.status(msg);
+        }
+
         // input
-        if event::poll(Duration::from_millis(10))?
-            && let Event::Key(key) = event::read()? {
-            match key.code {
-                KeyCode::Char('q') | KeyCode::Char('Q') => break,
-                KeyCode::Char('c') if key.modifiers.contains(KeyModifiers::CONTROL) => break,
-                KeyCode::Left => { app.selected = app.selected.saturating_sub(1); }
-                KeyCode::Right => { app.selected = (app.selected + 1).min(commands.len()-1); }
-                KeyCode::Tab => { if !commands.is_empty() { app.selected = (app.selected + 1) % commands.len(); } }
-                KeyCode::BackTab => { if !commands.is_empty() { app.selected = if app.selected == 0 { commands.len()-1 } else { app.selected - 1 }; } }
+        if event::poll(Duration::from_millis(10))? {
+            match event::read()? {
+                Event::Key(key) if app.searching.is_some() => match key.code {
+                    KeyCode::Esc => { app.searching = None; app.query = None; }
+                    KeyCode::Enter => {
+                        // Commit the search; the current query stays highlighted
+                        // and `n` searches further back from here.
+                        let q = app.searching.take().unwrap_or_default();
+                        app.query = if q.is_empty() { None } else { Some(q) };
+                    }
+                    KeyCode::Backspace => {
+                        if let Some(b) = app.searching.as_mut() { b.pop(); }
+                        let q = app.searching.clone().unwrap_or_default();
+                        app.query = if q.is_empty() { None } else { Some(q.clone()) };
+                        app.search_incremental(&q);
+                    }
+                    KeyCode::Char(c) => {
+                        if let Some(b) = app.searching.as_mut() { b.push(c); }
+                        let q = app.searching.clone().unwrap_or_default();
+                        app.query = Some(q.clone());
+                        app.search_incremental(&q);
+                    }
+                    _ => {}
+                },
+                Event::Key(key) => {
+                    let (tc, tr) = terminal::size().unwrap_or((80, 24));
+                    let page = pane_dims(tc, tr).0 as usize;
+                    match key.code {
+                        KeyCode::Char('q') | KeyCode::Char('Q') => break,
+                        KeyCode::Char('c') if key.modifiers.contains(KeyModifiers::CONTROL) => break,
+                        KeyCode::Left => { app.selected = app.selected.saturating_sub(1); }
+                        KeyCode::Right => { app.selected = (app.selected + 1).min(commands.len()-1); }
+                        KeyCode::Tab => { if !commands.is_empty() { app.selected = (app.selected + 1) % commands.len(); } }
+                        KeyCode::BackTab => { if !commands.is_empty() { app.selected = if app.selected == 0 { commands.len()-1 } else { app.selected - 1 }; } }
+                        KeyCode::PageUp => app.scroll_up(page),
+                        KeyCode::PageDown => app.scroll_down(page),
+                        KeyCode::Up => app.scroll_up(1),
+                        KeyCode::Down => app.scroll_down(1),
+                        KeyCode::Home => app.scroll_top(),
+                        KeyCode::End => app.scroll_bottom(),
+                        KeyCode::Char('/') => { app.searching = Some(String::new()); }
+                        KeyCode::Char('n') => { if let Some(q) = app.query.clone() { app.search_next(&q); } }
+                        _ => {}
+                    }
+                }
+                Event::Resize(cols, rows) => {
+                    let (pane_rows, pane_cols) = pane_dims(cols, rows);
+                    app.set_size(pane_rows, pane_cols);
+                    #[cfg(unix)]
+                    {
+                        let ws = pty::pane_winsize(cols, rows);
+                        for m in masters.lock().unwrap().iter().flatten() {
+                            pty::resize(*m, &ws);
+                        }
+                    }
+                }
                 _ => {}
             }
         }
@@ -199,6 +924,37 @@ pub async fn run(commands: Vec<String>) -> Result<i32> {
 
     // gather exit codes
     let mut worst = 0;
-    for h in join_handles { if let Ok(Ok(code)) = h.await && code != 0 { worst = code; } }
+    for h in join_handles { if let Ok(code) = h.await && code != 0 { worst = code; } }
     Ok(if worst < 0 { 1 } else { worst })
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn fmt_elapsed_sub_minute() {
+        assert_eq!(fmt_elapsed(std::time::Duration::from_millis(2300)), "2.3s");
+        assert_eq!(fmt_elapsed(std::time::Duration::from_millis(50)), "0.0s");
+    }
+
+    #[test]
+    fn fmt_elapsed_over_a_minute() {
+        assert_eq!(fmt_elapsed(std::time::Duration::from_secs(65)), "1m05s");
+        assert_eq!(fmt_elapsed(std::time::Duration::from_secs(600)), "10m00s");
+    }
+
+    #[test]
+    fn strip_ansi_drops_csi_and_osc() {
+        assert_eq!(strip_ansi("\x1b[31mred\x1b[0m"), "red");
+        assert_eq!(strip_ansi("\x1b]0;title\x07done"), "done");
+        assert_eq!(strip_ansi("plain"), "plain");
+    }
+
+    #[test]
+    fn log_stem_sanitizes_and_trims() {
+        assert_eq!(log_stem("cargo watch -x run"), "cargo_watch__x_run");
+        assert_eq!(log_stem("  ./build.sh  "), "build_sh");
+        assert_eq!(log_stem("npm run dev"), "npm_run_dev");
+    }
+}
@@ -0,0 +1,176 @@
+use std::path::Path;
+use std::sync::mpsc::{channel, RecvTimeoutError};
+use std::sync::atomic::Ordering;
+use std::time::Duration;
+
+use globset::{Glob, GlobSet, GlobSetBuilder};
+use ignore::gitignore::{Gitignore, GitignoreBuilder};
+use notify::{Event, RecommendedWatcher, RecursiveMode, Watcher};
+
+use crate::config::CommandSpec;
+use crate::runner;
+
+/// Coalesce bursts of filesystem events within this window into a single rerun.
+const DEBOUNCE: Duration = Duration::from_millis(100);
+
+/// Run the set once, then re-run it every time a watched path changes.
+///
+/// Events that fall inside `.gitignore`/`.ignore`, `target/`, or `.git/` are
+/// dropped, and — when `globs` is non-empty — only paths matching one of the
+/// globs trigger a rerun. Each trigger cancels the still-running children of the
+/// previous run before relaunching the whole set, the way `cargo watch` does.
+pub fn watch_loop(commands: Vec<CommandSpec>, globs: Vec<String>) -> i32 {
+    let root = Path::new(".");
+    let ignore = build_ignore(root);
+    let globset = build_globset(&globs);
+
+    let (tx, rx) = channel::<notify::Result<Event>>();
+    let mut watcher: RecommendedWatcher = match notify::recommended_watcher(tx) {
+        Ok(w) => w,
+        Err(e) => {
+            eprintln!("watch: failed to initialize watcher: {e}");
+            return 1;
+        }
+    };
+    if let Err(e) = watcher.watch(root, RecursiveMode::Recursive) {
+        eprintln!("watch: failed to watch '.': {e}");
+        return 1;
+    }
+
+    // `spawn_set` returns (join handle, cancel flag) for the in-flight run.
+    let mut run = runner::spawn_set(commands.clone());
+    loop {
+        match rx.recv() {
+            Ok(Ok(event)) => {
+                if !is_relevant(&event, &ignore, &globset) {
+                    continue;
+                }
+                // Coalesce any events that arrive during the debounce window.
+                drain_for(&rx, DEBOUNCE, &ignore, &globset);
+
+                // SIGTERM the previous run's children and wait for it to drain.
+                run.1.store(true, Ordering::SeqCst);
+                let _ = run.0.join();
+
+                eprintln!("[watch] change detected, re-running set");
+                run = runner::spawn_set(commands.clone());
+            }
+            Ok(Err(e)) => eprintln!("[watch] error: {e}"),
+            Err(_) => break,
+        }
+    }
+
+    run.1.store(true, Ordering::SeqCst);
+    run.0.join().unwrap_or(1)
+}
+
+/// Swallow further events until `window` passes without a *relevant* one, so a
+/// burst of source writes becomes a single rerun. Irrelevant events (under
+/// `target/`, ignored paths, non-matching globs) do not extend the window, so a
+/// build churning inside the quiet period can't keep postponing the rerun.
+fn drain_for(
+    rx: &std::sync::mpsc::Receiver<notify::Result<Event>>,
+    window: Duration,
+    ignore: &Gitignore,
+    globset: &Option<GlobSet>,
+) {
+    let mut deadline = std::time::Instant::now() + window;
+    loop {
+        let now = std::time::Instant::now();
+        if now >= deadline {
+            break;
+        }
+        match rx.recv_timeout(deadline - now) {
+            Ok(Ok(event)) => {
+                if is_relevant(&event, ignore, globset) {
+                    deadline = std::time::Instant::now() + window;
+                }
+            }
+            Ok(Err(_)) => {}
+            Err(RecvTimeoutError::Timeout) | Err(RecvTimeoutError::Disconnected) => break,
+        }
+    }
+}
+
+/// Decide whether an event should trigger a rerun.
+fn is_relevant(event: &Event, ignore: &Gitignore, globset: &Option<GlobSet>) -> bool {
+    event.paths.iter().any(|p| {
+        // Check the path and each parent directory, so a file inside an
+        // ignored directory (e.g. `target/debug/x`) is dropped too.
+        let ignored = p.ancestors().enumerate().any(|(i, a)| {
+            !a.as_os_str().is_empty() && ignore.matched(a, i > 0 || a.is_dir()).is_ignore()
+        });
+        if ignored {
+            return false;
+        }
+        match globset {
+            Some(set) => set.is_match(p),
+            None => true,
+        }
+    })
+}
+
+/// Build the ignore matcher, always skipping `target/` and `.git/`.
+fn build_ignore(root: &Path) -> Gitignore {
+    let mut builder = GitignoreBuilder::new(root);
+    let _ = builder.add(root.join(".gitignore"));
+    let _ = builder.add(root.join(".ignore"));
+    let _ = builder.add_line(None, "target/");
+    let _ = builder.add_line(None, ".git/");
+    builder.build().unwrap_or_else(|_| Gitignore::empty())
+}
+
+/// Compile the user-supplied globs into a matcher, or `None` to watch everything.
+fn build_globset(globs: &[String]) -> Option<GlobSet> {
+    if globs.is_empty() {
+        return None;
+    }
+    let mut builder = GlobSetBuilder::new();
+    for g in globs {
+        match Glob::new(g) {
+            Ok(glob) => {
+                builder.add(glob);
+            }
+            Err(e) => eprintln!("[watch] ignoring invalid glob '{g}': {e}"),
+        }
+    }
+    builder.build().ok()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use notify::{Event, EventKind};
+    use std::path::PathBuf;
+
+    fn event(path: &str) -> Event {
+        Event {
+            kind: EventKind::Modify(notify::event::ModifyKind::Any),
+            paths: vec![PathBuf::from(path)],
+            attrs: Default::default(),
+        }
+    }
+
+    #[test]
+    fn ignore_skips_target_and_git() {
+        let ig = build_ignore(Path::new("."));
+        assert!(ig.matched_path_or_any_parents("target/debug/ghop", false).is_ignore());
+        assert!(ig.matched_path_or_any_parents(".git/HEAD", false).is_ignore());
+        assert!(!ig.matched("src/main.rs", false).is_ignore());
+    }
+
+    #[test]
+    fn relevant_drops_ignored_paths() {
+        let ig = build_ignore(Path::new("."));
+        assert!(!is_relevant(&event("target/debug/x"), &ig, &None));
+        assert!(is_relevant(&event("src/watch.rs"), &ig, &None));
+    }
+
+    #[test]
+    fn relevant_honors_globs() {
+        let ig = build_ignore(Path::new("."));
+        let globs = build_globset(&["*.rs".to_string()]);
+        assert!(is_relevant(&event("src/watch.rs"), &ig, &globs));
+        assert!(!is_relevant(&event("notes.txt"), &ig, &globs));
+    }
+}
@@ -52,3 +52,42 @@ fn missing_file_is_error() {
     let err = load_commands_from_yaml("/no/such/file/ghop.yml", "x").unwrap_err();
     assert!(err.starts_with("Failed to read YAML file"), "err was: {err}");
 }
+
+#[test]
+fn unknown_dependency_is_error() {
+    let mut tf = tempfile::NamedTempFile::new().unwrap();
+    use std::io::Write;
+    writeln!(tf, r#"sets:
+  s:
+    - {{ command: "echo a", name: "a", depends_on: ["ghost"] }}
+"#).unwrap();
+    let err = load_commands_from_yaml(tf.path().to_str().unwrap(), "s").unwrap_err();
+    assert!(err.contains("unknown command 'ghost'"), "err was: {err}");
+}
+
+#[test]
+fn dependency_cycle_is_error() {
+    let mut tf = tempfile::NamedTempFile::new().unwrap();
+    use std::io::Write;
+    writeln!(tf, r#"sets:
+  s:
+    - {{ command: "echo a", name: "a", depends_on: ["b"] }}
+    - {{ command: "echo b", name: "b", depends_on: ["a"] }}
+"#).unwrap();
+    let err = load_commands_from_yaml(tf.path().to_str().unwrap(), "s").unwrap_err();
+    assert!(err.contains("Dependency cycle"), "err was: {err}");
+}
+
+#[test]
+fn valid_dependencies_parse() {
+    let mut tf = tempfile::NamedTempFile::new().unwrap();
+    use std::io::Write;
+    writeln!(tf, r#"sets:
+  s:
+    - {{ command: "echo db", name: "db" }}
+    - {{ command: "echo api", name: "api", depends_on: ["db"] }}
+"#).unwrap();
+    let cmds = load_commands_from_yaml(tf.path().to_str().unwrap(), "s").unwrap();
+    assert_eq!(cmds.len(), 2);
+    assert_eq!(cmds[1].depends_on, vec!["db".to_string()]);
+}